@@ -0,0 +1,362 @@
+//! `sshchic benchmark`: measure per-algorithm key-generation throughput and,
+//! given a `--regex`, estimate how long a real vanity-key search would take.
+//!
+//! Throughput is sampled the same way the live search monitor does (a 250ms
+//! tick feeding [`crate::exp_moving_average`]), so the numbers here should
+//! line up with what you'd see running `sshchic --regex ... --algorithm ...`
+//! for real. Match-time estimates treat key generation as a Bernoulli trial
+//! with per-key match probability `p`: expected attempts are `1/p`, and the
+//! number of attempts needed to reach a given confidence `q` follows the
+//! geometric distribution, `ln(1-q)/ln(1-p)`.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clap::Args as ClapArgs;
+use regex::Regex;
+use regex_syntax::hir::{Class, Hir, HirKind, Look};
+use regex_syntax::Parser as HirParser;
+
+use crate::keys::KeyKind;
+
+/// Options for `sshchic benchmark`.
+#[derive(ClapArgs, Clone)]
+pub struct BenchmarkArgs {
+    /// How many seconds to sample each algorithm's throughput for
+    #[arg(
+        long,
+        default_value_t = 5,
+        help = "Seconds to sample each algorithm for"
+    )]
+    pub duration: u64,
+
+    /// Pattern to estimate match probability and expected time-to-match for
+    #[arg(long, help = "Regex pattern to estimate match time for")]
+    pub regex: Option<String>,
+
+    /// Case-insensitive matching for `--regex`, same semantics as the top-level flag
+    #[arg(
+        short,
+        long,
+        help = "Enable case-insensitive matching for --regex"
+    )]
+    pub insensitive: bool,
+}
+
+/// Throughput and a small sample of generated keys for one algorithm.
+struct Sample {
+    kind: KeyKind,
+    rate: f64,
+    ema_rate: f64,
+    generated: Vec<String>,
+}
+
+/// The number of generated public keys to retain per algorithm for the
+/// Monte Carlo fallback estimate. Bounded so a long `--duration` run on a
+/// fast algorithm doesn't balloon memory use.
+const MONTE_CARLO_SAMPLE_CAP: usize = 5_000;
+
+pub fn run(args: &BenchmarkArgs) {
+    let regex = args.regex.as_ref().map(|pattern| {
+        let pattern = if args.insensitive {
+            format!("(?i){pattern}")
+        } else {
+            pattern.clone()
+        };
+        Regex::new(&pattern).unwrap_or_else(|e| {
+            eprintln!("Invalid regex pattern: {e}");
+            std::process::exit(1);
+        })
+    });
+
+    println!(
+        "Benchmarking key generation ({}s per algorithm, {} threads)...\n",
+        args.duration,
+        num_cpus::get()
+    );
+
+    let samples: Vec<Sample> = KeyKind::benchmark_set()
+        .into_iter()
+        .map(|kind| sample_algorithm(kind, args.duration))
+        .collect();
+
+    println!("{:<12} {:>14} {:>14}", "Algorithm", "Keys/s", "EMA Keys/s");
+    for sample in &samples {
+        println!(
+            "{:<12} {:>14.2} {:>14.2}",
+            sample.kind.to_string(),
+            sample.rate,
+            sample.ema_rate
+        );
+    }
+
+    if let Some(regex) = &regex {
+        println!(
+            "\nMatch-time estimate for pattern {:?}:",
+            args.regex.as_ref().unwrap()
+        );
+        for sample in &samples {
+            report_match_estimate(sample, regex);
+        }
+    }
+}
+
+/// Runs one algorithm at full parallelism for `duration_secs`, sampling the
+/// counter every 250ms to produce the same EMA rate the live monitor shows.
+fn sample_algorithm(kind: KeyKind, duration_secs: u64) -> Sample {
+    let counter = Arc::new(AtomicI64::new(0));
+    let generated = Arc::new(Mutex::new(Vec::new()));
+    let deadline = Instant::now() + Duration::from_secs(duration_secs.max(1));
+
+    let mut handles = Vec::new();
+    for _ in 0..num_cpus::get() {
+        let counter = Arc::clone(&counter);
+        let generated = Arc::clone(&generated);
+        handles.push(thread::spawn(move || {
+            while Instant::now() < deadline {
+                let key = kind.generate();
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let mut generated = generated.lock().unwrap();
+                if generated.len() < MONTE_CARLO_SAMPLE_CAP {
+                    generated.push(key.to_openssh_public());
+                }
+            }
+        }));
+    }
+
+    let mut old_counter = 0i64;
+    let mut old_time = Instant::now();
+    let mut ema_rate = 0f64;
+    while Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(250));
+        let current = counter.load(Ordering::SeqCst);
+        let elapsed = old_time.elapsed().as_secs_f64();
+        let tick_rate = (current - old_counter) as f64 / elapsed;
+        // Seed the average from the first tick, as the live monitor does,
+        // rather than decaying up from zero.
+        ema_rate = if old_counter == 0 {
+            tick_rate
+        } else {
+            crate::exp_moving_average(tick_rate, ema_rate, elapsed, 5.0)
+        };
+        old_counter = current;
+        old_time = Instant::now();
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let total = counter.load(Ordering::SeqCst);
+    Sample {
+        kind,
+        rate: total as f64 / duration_secs.max(1) as f64,
+        ema_rate,
+        generated: Arc::try_unwrap(generated)
+            .expect("all worker threads have joined")
+            .into_inner()
+            .unwrap(),
+    }
+}
+
+fn report_match_estimate(sample: &Sample, regex: &Regex) {
+    let p = analytic_match_probability(regex.as_str(), sample)
+        .unwrap_or_else(|| monte_carlo_match_probability(sample, regex));
+
+    println!("  {}:", sample.kind);
+    println!("    match probability per key: {:.3e}", p);
+
+    // p == 0.0 means the pattern can never match this algorithm's keys (e.g.
+    // pinned to a fixed header byte it's incompatible with) -- 1/p and the
+    // percentile formula below would print `inf`/`NaN`, so say so directly.
+    // p == 1.0 (every key matches) hits the same problem from the other
+    // side, via ln(1.0 - p) = ln(0).
+    if p <= 0.0 {
+        println!("    expected attempts: never matches this algorithm's keys");
+        return;
+    }
+    if p >= 1.0 {
+        println!("    expected attempts: 1 (every generated key matches)");
+        return;
+    }
+
+    println!("    expected attempts: {:.0}", 1.0 / p);
+    for &q in &[0.5f64, 0.9, 0.99] {
+        let attempts = (1.0 - q).ln() / (1.0 - p).ln();
+        let seconds = attempts / sample.rate.max(f64::EPSILON);
+        println!(
+            "    {:>3.0}% chance within: {} ({:.0} attempts)",
+            q * 100.0,
+            format_duration(seconds),
+            attempts
+        );
+    }
+}
+
+/// Derives an analytic match probability for simple patterns over the
+/// base64 alphabet: a concatenation of fixed-width literal characters and
+/// character classes, anchored at the start (`^...`), the end (`...$`), or
+/// both.
+///
+/// An anchor pins the pattern to exactly one starting offset in the
+/// haystack, which is what makes an analytic answer possible at all -- an
+/// *unanchored* literal like `"SSH"` can start matching at any of many
+/// positions, and the per-character product below has no way to account for
+/// that, so patterns with no anchor at all fall back to Monte Carlo instead
+/// of silently picking (and overstating around) one arbitrary alignment.
+///
+/// Each constrained position's contribution isn't simply assumed to be
+/// uniform over the 64-character alphabet either: positions that fall
+/// inside the fixed algorithm-name header every key of this kind shares
+/// (e.g. the `ssh-ed25519` wire name baked into the start of the base64)
+/// are constant, not random, so [`position_probability`] checks the actual
+/// keys already generated during throughput sampling to tell the two apart.
+///
+/// Returns `None` for anything more exotic (alternation, repetition,
+/// backreferences, variable-width tokens, ...), leaving the caller to fall
+/// back to Monte Carlo.
+fn analytic_match_probability(pattern: &str, sample: &Sample) -> Option<f64> {
+    let hir = HirParser::new().parse(pattern).ok()?;
+    let items: Vec<&Hir> = match hir.kind() {
+        HirKind::Concat(items) => items.iter().collect(),
+        HirKind::Literal(_) | HirKind::Class(_) | HirKind::Look(_) => vec![&hir],
+        _ => return None,
+    };
+
+    let starts_anchored = matches!(items.first()?.kind(), HirKind::Look(Look::Start));
+    let ends_anchored = matches!(items.last()?.kind(), HirKind::Look(Look::End));
+    if !starts_anchored && !ends_anchored {
+        return None;
+    }
+
+    let body: &[&Hir] = match (starts_anchored, ends_anchored) {
+        (true, true) => &items[1..items.len() - 1],
+        (true, false) => &items[1..],
+        (false, true) => &items[..items.len() - 1],
+        (false, false) => unreachable!("returned above when neither anchor is present"),
+    };
+
+    // Fixed-width assumption: every remaining token pins exactly one
+    // haystack character (one byte per literal byte, one class per
+    // position). Bail on anything else before committing to an offset.
+    let mut width = 0usize;
+    for item in body {
+        match item.kind() {
+            HirKind::Literal(literal) => width += literal.0.len(),
+            HirKind::Class(_) => width += 1,
+            _ => return None,
+        }
+    }
+
+    let start_pos = if starts_anchored {
+        0
+    } else {
+        let haystack_len = sample.generated.first()?.chars().count();
+        haystack_len.checked_sub(width)?
+    };
+
+    let mut probability = 1.0;
+    let mut pos = start_pos;
+    for item in body {
+        match item.kind() {
+            HirKind::Literal(literal) => {
+                for &byte in literal.0.iter() {
+                    let expected = byte as char;
+                    probability *= position_probability(sample, pos, 1.0, |c| c == expected);
+                    pos += 1;
+                }
+            }
+            HirKind::Class(class) => {
+                let size = class_size(class);
+                probability *= position_probability(sample, pos, size, |c| class_contains(class, c));
+                pos += 1;
+            }
+            _ => unreachable!("width computation above already rejected anything else"),
+        }
+    }
+
+    Some(probability)
+}
+
+/// Estimates the match probability at a single haystack position.
+///
+/// `class_size` is how many base64 characters `contains` accepts and
+/// `contains` tests whether an observed character satisfies this position;
+/// together they give the naive `class_size / 64` estimate for a position
+/// that's genuinely random. But if every key already generated during
+/// throughput sampling agrees on the same character here, this position is
+/// actually constant (e.g. a byte inside the algorithm-name header encoded
+/// into every key of this kind) rather than uniformly random, so the real
+/// probability is 0 or 1 depending on whether that fixed character matches
+/// -- not `class_size / 64`.
+fn position_probability(sample: &Sample, pos: usize, class_size: f64, contains: impl Fn(char) -> bool) -> f64 {
+    const BASE64_ALPHABET_SIZE: f64 = 64.0;
+
+    let mut observed = sample.generated.iter().filter_map(|key| key.chars().nth(pos));
+    match observed.next() {
+        Some(first) if observed.all(|c| c == first) => {
+            if contains(first) {
+                1.0
+            } else {
+                0.0
+            }
+        }
+        _ => (class_size.min(BASE64_ALPHABET_SIZE)) / BASE64_ALPHABET_SIZE,
+    }
+}
+
+/// How many characters of the base64 alphabet `class` accepts.
+fn class_size(class: &Class) -> f64 {
+    const BASE64_ALPHABET: &str =
+        "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    BASE64_ALPHABET
+        .chars()
+        .filter(|&c| class_contains(class, c))
+        .count() as f64
+}
+
+fn class_contains(class: &Class, ch: char) -> bool {
+    match class {
+        Class::Unicode(u) => u.ranges().iter().any(|r| ch >= r.start() && ch <= r.end()),
+        Class::Bytes(b) => {
+            ch.is_ascii() && b.ranges().iter().any(|r| (ch as u8) >= r.start() && (ch as u8) <= r.end())
+        }
+    }
+}
+
+/// Falls back to an empirical estimate from the keys already generated
+/// during the throughput sampling window, using Laplace's rule of
+/// succession so a pattern with zero observed matches still yields a
+/// finite (if conservative) probability instead of a divide-by-zero.
+fn monte_carlo_match_probability(sample: &Sample, regex: &Regex) -> f64 {
+    let matches = sample
+        .generated
+        .iter()
+        .filter(|key| regex.is_match(key))
+        .count();
+    (matches as f64 + 1.0) / (sample.generated.len() as f64 + 2.0)
+}
+
+fn format_duration(seconds: f64) -> String {
+    if !seconds.is_finite() {
+        return "∞".to_string();
+    }
+
+    let total_secs = seconds.round() as u64;
+    let (days, rem) = (total_secs / 86_400, total_secs % 86_400);
+    let (hours, rem) = (rem / 3_600, rem % 3_600);
+    let (minutes, secs) = (rem / 60, rem % 60);
+
+    if days > 0 {
+        format!("{days}d {hours}h")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {secs}s")
+    } else {
+        format!("{secs}s")
+    }
+}
@@ -0,0 +1,278 @@
+//! Key algorithm support: generation, OpenSSH encoding, and fingerprinting
+//! for every SSH key type SSHChic can search against.
+//!
+//! [`KeyKind`] is the single dispatch point the worker loop uses instead of
+//! hardcoding ED25519: pick a kind from `--algorithm`, call [`KeyKind::generate`]
+//! in the hot loop, then render the result with [`GeneratedKey::to_openssh_public`]
+//! or [`GeneratedKey::fingerprint`] in place of the original ED25519-only helpers.
+
+use std::fmt;
+use std::str::FromStr;
+
+use base64::{engine::general_purpose, Engine as _};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use ssh_key::{
+    private::{KeypairData, RsaKeypair},
+    Algorithm, Cipher, EcdsaCurve, Kdf, LineEnding, PrivateKey,
+};
+
+/// Which digest to render a key's fingerprint with.
+///
+/// SHA256 is the modern OpenSSH default; MD5 is the legacy colon-hex form
+/// still printed by `ssh-keygen -l -E md5`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HashAlg {
+    Sha256,
+    Md5,
+}
+
+/// The minimum RSA modulus size we'll generate. `ssh_key`/OpenSSH both
+/// refuse anything smaller, so reject it up front at parse time rather than
+/// silently generating a different size than the user asked for.
+const MIN_RSA_BITS: u32 = 1024;
+
+/// The key algorithm a worker thread should generate and test.
+///
+/// Variants mirror what `--algorithm` accepts on the command line. `Rsa`
+/// carries its modulus size in bits since RSA (unlike the others) doesn't
+/// have a single fixed key size.
+///
+/// **Deviation from the original request**: the request that introduced
+/// this module also asked for `ed448`. It's deliberately not offered here
+/// -- OpenSSH defines no `ssh-ed448` wire type, so there's no OpenSSH
+/// private/public key encoding to produce, and an earlier attempt at one
+/// just emitted an unloadable made-up format. Flagging this back to
+/// whoever filed the request rather than silently shipping a narrower
+/// `--algorithm` set than asked for: if Ed448 vanity keys are still wanted,
+/// they'd need to be usable outside OpenSSH entirely (e.g. raw key export),
+/// which is a different feature than "another `--algorithm` choice".
+#[derive(Debug, Clone, Copy)]
+pub enum KeyKind {
+    Ed25519,
+    EcdsaP256,
+    EcdsaP384,
+    EcdsaP521,
+    Rsa(u32),
+}
+
+impl KeyKind {
+    /// Generates a fresh key pair of this kind.
+    ///
+    /// Everything here goes through `ssh_key`'s own types, which already
+    /// know how to talk to the underlying `p256`/`p384`/`p521`/`rsa` crates.
+    /// `Rsa` generates the keypair at the requested modulus size directly
+    /// via [`RsaKeypair::random`] rather than `PrivateKey::random`'s fixed
+    /// default, since the whole point of `rsa-<bits>` is to pick a size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if key generation fails (e.g. the RNG is broken). This mirrors
+    /// the `.expect()` style the rest of the generator uses for conditions
+    /// that should never happen outside of a broken environment.
+    pub fn generate(&self) -> GeneratedKey {
+        let mut rng = rand::thread_rng();
+
+        let private = match self {
+            KeyKind::Ed25519 => {
+                PrivateKey::random(&mut rng, Algorithm::Ed25519).expect("failed to generate Ed25519 key")
+            }
+            KeyKind::EcdsaP256 => PrivateKey::random(
+                &mut rng,
+                Algorithm::Ecdsa {
+                    curve: EcdsaCurve::NistP256,
+                },
+            )
+            .expect("failed to generate ECDSA P-256 key"),
+            KeyKind::EcdsaP384 => PrivateKey::random(
+                &mut rng,
+                Algorithm::Ecdsa {
+                    curve: EcdsaCurve::NistP384,
+                },
+            )
+            .expect("failed to generate ECDSA P-384 key"),
+            KeyKind::EcdsaP521 => PrivateKey::random(
+                &mut rng,
+                Algorithm::Ecdsa {
+                    curve: EcdsaCurve::NistP521,
+                },
+            )
+            .expect("failed to generate ECDSA P-521 key"),
+            KeyKind::Rsa(bits) => {
+                let keypair = RsaKeypair::random(&mut rng, *bits as usize)
+                    .expect("failed to generate RSA key");
+                PrivateKey::new(KeypairData::Rsa(keypair), "").expect("failed to wrap RSA key")
+            }
+        };
+
+        GeneratedKey(private)
+    }
+
+    /// A representative set of algorithms for `sshchic benchmark` to sample.
+    /// RSA uses 3072 bits, `ssh-keygen`'s current default modulus size.
+    pub fn benchmark_set() -> [KeyKind; 5] {
+        [
+            KeyKind::Ed25519,
+            KeyKind::EcdsaP256,
+            KeyKind::EcdsaP384,
+            KeyKind::EcdsaP521,
+            KeyKind::Rsa(3072),
+        ]
+    }
+
+    /// The filename this kind's private key is saved under, matching
+    /// `ssh-keygen`'s defaults (`id_ed25519`, `id_rsa`, ...). ECDSA doesn't
+    /// encode the curve in the default filename, same as `ssh-keygen`.
+    pub fn file_stem(&self) -> &'static str {
+        match self {
+            KeyKind::Ed25519 => "id_ed25519",
+            KeyKind::EcdsaP256 | KeyKind::EcdsaP384 | KeyKind::EcdsaP521 => "id_ecdsa",
+            KeyKind::Rsa(_) => "id_rsa",
+        }
+    }
+}
+
+impl fmt::Display for KeyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyKind::Ed25519 => write!(f, "ed25519"),
+            KeyKind::EcdsaP256 => write!(f, "ecdsa-p256"),
+            KeyKind::EcdsaP384 => write!(f, "ecdsa-p384"),
+            KeyKind::EcdsaP521 => write!(f, "ecdsa-p521"),
+            KeyKind::Rsa(bits) => write!(f, "rsa-{bits}"),
+        }
+    }
+}
+
+impl FromStr for KeyKind {
+    type Err = String;
+
+    /// Parses the `--algorithm` value. `rsa-<bits>` is the only variant
+    /// with a parameter, so it's matched by prefix rather than listed as
+    /// a fixed `clap::ValueEnum` variant. A size below [`MIN_RSA_BITS`] is
+    /// rejected here rather than silently generating a different size than
+    /// the one requested.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ed25519" => Ok(KeyKind::Ed25519),
+            "ecdsa-p256" => Ok(KeyKind::EcdsaP256),
+            "ecdsa-p384" => Ok(KeyKind::EcdsaP384),
+            "ecdsa-p521" => Ok(KeyKind::EcdsaP521),
+            _ => {
+                if let Some(bits) = s.strip_prefix("rsa-") {
+                    let bits = bits
+                        .parse::<u32>()
+                        .map_err(|_| format!("invalid RSA key size: {bits}"))?;
+                    if bits < MIN_RSA_BITS {
+                        return Err(format!(
+                            "RSA key size must be at least {MIN_RSA_BITS} bits (got {bits})"
+                        ));
+                    }
+                    Ok(KeyKind::Rsa(bits))
+                } else {
+                    Err(format!(
+                        "unknown algorithm '{s}' (expected ed25519, ecdsa-p256, ecdsa-p384, \
+                         ecdsa-p521, or rsa-<bits>)"
+                    ))
+                }
+            }
+        }
+    }
+}
+
+/// A freshly generated key pair, wrapping `ssh_key`'s own `PrivateKey` so
+/// the rest of the crate renders OpenSSH text and computes fingerprints
+/// through one place instead of depending on `ssh_key` everywhere a key is
+/// passed around.
+pub struct GeneratedKey(PrivateKey);
+
+impl GeneratedKey {
+    /// Renders the public half in `authorized_keys` / `.pub` format.
+    pub fn to_openssh_public(&self) -> String {
+        self.0.public_key().to_string()
+    }
+
+    /// Renders the private half as an OpenSSH-format private key, optionally
+    /// passphrase-encrypted.
+    ///
+    /// Without a passphrase this is identical to the old unconditional
+    /// `to_openssh` call. With one, the key is encrypted AES256-CTR under a
+    /// bcrypt-derived key before being serialized, exactly like `ssh-keygen`.
+    pub fn to_openssh_private(&self, passphrase: Option<(&str, u32)>) -> String {
+        let encrypted;
+        let private = match passphrase {
+            Some((passphrase, kdf_rounds)) => {
+                encrypted = encrypt_private_key(&self.0, passphrase, kdf_rounds);
+                &encrypted
+            }
+            None => &self.0,
+        };
+        private
+            .to_openssh(LineEnding::LF)
+            .expect("failed to encode private key")
+            .to_string()
+    }
+
+    /// The SSH wire encoding of the public key: 4-byte-length-prefixed
+    /// algorithm name followed by the 4-byte-length-prefixed key material
+    /// (e.g. for ED25519: `"ssh-ed25519"` then the 32 key bytes). This is
+    /// the exact blob `ssh-keygen -l` hashes to produce a fingerprint --
+    /// *not* the raw key bytes and *not* the base64 text in the `.pub` file.
+    fn wire_blob(&self) -> Vec<u8> {
+        self.0
+            .public_key()
+            .to_bytes()
+            .expect("failed to encode public key blob")
+    }
+
+    /// Computes this key's fingerprint the way `ssh-keygen -l` displays it:
+    /// hashing the SSH wire blob (not the raw key bytes), rendering SHA256
+    /// as unpadded base64 and MD5 as lowercase colon-separated hex.
+    pub fn fingerprint(&self, alg: HashAlg) -> String {
+        let blob = self.wire_blob();
+        match alg {
+            HashAlg::Sha256 => {
+                let mut hasher = Sha256::new();
+                hasher.update(&blob);
+                format!(
+                    "SHA256:{}",
+                    general_purpose::STANDARD_NO_PAD.encode(hasher.finalize())
+                )
+            }
+            HashAlg::Md5 => {
+                let digest = md5::compute(&blob);
+                let hex = digest
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(":");
+                format!("MD5:{hex}")
+            }
+        }
+    }
+}
+
+/// Encrypts `private` under `passphrase` using AES256-CTR with a
+/// bcrypt-derived key, the same scheme `ssh-keygen` uses for OpenSSH private
+/// keys. Built from `ssh_key`'s `Cipher`/`Kdf` primitives directly (rather
+/// than `Kdf::new`, which hardcodes 16 rounds) so `--kdf-rounds` actually
+/// changes the bcrypt cost factor: a fresh random salt and `kdf_rounds` are
+/// assembled into a `Kdf::Bcrypt` by hand, alongside a random `checkint`
+/// (the value OpenSSH's format repeats twice in the encrypted payload to
+/// verify the passphrase on load).
+fn encrypt_private_key(private: &PrivateKey, passphrase: &str, kdf_rounds: u32) -> PrivateKey {
+    let mut rng = rand::thread_rng();
+
+    let mut salt = [0u8; 16];
+    rng.fill_bytes(&mut salt);
+    let kdf = Kdf::Bcrypt {
+        salt: salt.to_vec(),
+        rounds: kdf_rounds,
+    };
+
+    let checkint = rng.next_u32();
+
+    private
+        .encrypt_with(Cipher::Aes256Ctr, kdf, checkint, passphrase)
+        .expect("failed to encrypt private key")
+}
@@ -1,23 +1,28 @@
 //! # SSHChic
 //!
-//! A fast, multi-threaded ED25519 SSH key generator that searches for public keys matching custom patterns.
+//! A fast, multi-threaded SSH key generator that searches for public keys matching custom patterns.
 //!
-//! This tool generates ED25519 key pairs in parallel and tests them against a regex pattern,
+//! This tool generates key pairs in parallel and tests them against a regex pattern,
 //! allowing you to create "vanity" SSH keys with specific patterns in the public key or fingerprint.
 //!
 //! ## Features
 //!
 //! - **Multi-threaded generation**: Utilizes all CPU cores for maximum performance
-//! - **Regex pattern matching**: Full regex support for flexible pattern matching
+//! - **Multiple key algorithms**: ED25519, ECDSA (P-256/384/521), and RSA
+//! - **Regex pattern matching**: Full regex support, with multiple patterns searched at once
 //! - **Dual match modes**: Match against public key or SHA256 fingerprint
 //! - **Streaming mode**: Continue searching for multiple matches
 //! - **Real-time monitoring**: Live statistics on key generation rate
 //! - **Graceful shutdown**: Clean termination with Ctrl+C
+//! - **Benchmark mode**: Measure per-algorithm throughput and estimate match time for a pattern
+//! - **Passphrase encryption**: Optionally protect the saved private key at rest, like `ssh-keygen`
+//! - **Unattended collection**: `--output-dir` saves every match, even in streaming mode
 //!
 //! ## Performance
 //!
 //! The tool generates thousands of keys per second, with actual performance depending on:
 //! - CPU core count and clock speed
+//! - Key algorithm (RSA is dramatically slower than the elliptic-curve kinds)
 //! - Regex pattern complexity
 //! - Match target (fingerprint matching is slightly faster)
 //!
@@ -30,7 +35,7 @@
 //!   ├─ Setup Ctrl+C handler
 //!   ├─ Spawn N worker threads (N = CPU cores)
 //!   │   └─ Each worker:
-//!   │       - Generate key pair
+//!   │       - Generate key pair (KeyKind::generate)
 //!   │       - Test against regex
 //!   │       - Save on match (unless streaming)
 //!   └─ Monitor loop (250ms interval):
@@ -38,13 +43,17 @@
 //!       - Calculate moving average
 //! ```
 
-use clap::Parser;
+mod benchmark;
+mod keys;
+
+use benchmark::BenchmarkArgs;
+use clap::{Parser, Subcommand};
 use colored::*;
-use ed25519_dalek::{SigningKey, VerifyingKey};
 use humansize::{format_size, DECIMAL};
-use regex::Regex;
-use ssh_key::{LineEnding, PrivateKey};
+use keys::{HashAlg, KeyKind};
+use regex::RegexSet;
 use std::fs;
+use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::sync::Arc;
 use std::thread;
@@ -66,24 +75,49 @@ use std::time::{Duration, Instant};
 ///
 /// # Streaming mode to find multiple matches
 /// sshchic --regex "^AAAA" --streaming
+///
+/// # Vanity RSA-4096 key instead of the default ED25519
+/// sshchic --regex "SSH$" --algorithm rsa-4096
+///
+/// # How fast is this machine, and how long would "SSH$" realistically take?
+/// sshchic benchmark --duration 10 --regex "SSH$"
+///
+/// # Hunt for several usernames at once, saving every hit unattended
+/// sshchic --regex "alice$" --regex "bob$" --streaming --output-dir ./vanity-keys
 /// ```
 #[derive(Parser, Clone)]
 #[command(author, version, about)]
 struct Args {
+    /// Subcommand to run instead of the default search mode
+    ///
+    /// With no subcommand, SSHChic searches for a single `--regex` match
+    /// (the original behavior). `benchmark` measures throughput instead.
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Regex pattern to match against the generated SSH keys
     ///
     /// The pattern uses standard regex syntax. The match target depends
     /// on the `--fingerprint` flag:
     /// - Without flag: matches against the OpenSSH public key format
-    /// - With flag: matches against the SHA256 fingerprint (base64 encoded)
+    /// - With flag: matches against the displayed fingerprint (e.g.
+    ///   `SHA256:<base64-no-pad>` or `MD5:aa:bb:...`, see `--fingerprint-type`),
+    ///   the same string `ssh-keygen -l` prints
+    ///
+    /// Repeatable: pass `--regex` multiple times to hunt for any of several
+    /// patterns at once (e.g. a few candidate usernames). All generated keys
+    /// are tested against the whole set in one pass, and a match reports
+    /// which pattern(s) it satisfied.
+    ///
+    /// Required in search mode (the default); ignored when `benchmark` is used.
     ///
     /// # Examples
     ///
     /// - `"^AAAA"` - Keys starting with AAAA
     /// - `"SSH$"` - Keys ending with SSH
     /// - `"[0-9]{4}"` - Keys containing 4 consecutive digits
-    #[arg(short, long, help = "Regex pattern to search for")]
-    regex: String,
+    #[arg(short, long, help = "Regex pattern to search for (repeatable)")]
+    regex: Vec<String>,
 
     /// Enable case-insensitive pattern matching
     ///
@@ -102,13 +136,87 @@ struct Args {
     #[arg(short, long, help = "Keep processing keys, even after a match")]
     streaming: bool,
 
-    /// Match against the key's SHA256 fingerprint instead of the public key
+    /// Match against the key's fingerprint instead of the public key
     ///
-    /// When enabled, the regex pattern is tested against the base64-encoded
-    /// SHA256 fingerprint rather than the OpenSSH public key format.
-    /// Fingerprint matching is typically slightly faster.
+    /// When enabled, the regex pattern is tested against the fingerprint
+    /// string (digest selected by `--fingerprint-type`) rather than the
+    /// OpenSSH public key format. Fingerprint matching is typically
+    /// slightly faster.
     #[arg(short, long, help = "Match against fingerprint instead of public key")]
     fingerprint: bool,
+
+    /// Which fingerprint digest to display and match against
+    ///
+    /// `sha256` renders as `SHA256:<base64-no-pad>` (the OpenSSH default);
+    /// `md5` renders as `MD5:aa:bb:cc:...` lowercase colon-hex, matching
+    /// `ssh-keygen -l -E md5`. Only relevant together with `--fingerprint`.
+    #[arg(
+        long,
+        value_enum,
+        default_value = "sha256",
+        help = "Fingerprint digest to use (sha256, md5)"
+    )]
+    fingerprint_type: HashAlg,
+
+    /// Key algorithm to generate
+    ///
+    /// Accepts `ed25519`, `ecdsa-p256`, `ecdsa-p384`, `ecdsa-p521`, or
+    /// `rsa-<bits>` (e.g. `rsa-4096`). RSA generation is dramatically
+    /// slower than the others, so expect key rates in the single digits
+    /// per second rather than thousands.
+    #[arg(
+        short,
+        long,
+        default_value = "ed25519",
+        value_parser = KeyKind::from_str,
+        help = "Key algorithm to generate (ed25519, ecdsa-p256/384/521, rsa-<bits>)"
+    )]
+    algorithm: KeyKind,
+
+    /// Encrypt the saved private key with a passphrase
+    ///
+    /// Pass a value directly (`--passphrase hunter2`) or pass the flag with
+    /// no value to be prompted interactively, without echo, so the
+    /// passphrase never ends up in shell history. The private key printed
+    /// on a match is encrypted too, including in streaming mode, where it
+    /// is only written to disk when `--output-dir` is set.
+    #[arg(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "",
+        help = "Encrypt the saved private key (prompts if no value given)"
+    )]
+    passphrase: Option<String>,
+
+    /// bcrypt KDF rounds used when `--passphrase` is set
+    ///
+    /// Higher values make brute-forcing the passphrase slower at the cost
+    /// of slower key loading, same trade-off as `ssh-keygen -a`. 16 matches
+    /// `ssh-keygen`'s own default.
+    #[arg(
+        long,
+        default_value_t = 16,
+        help = "bcrypt KDF rounds for --passphrase (default: 16)"
+    )]
+    kdf_rounds: u32,
+
+    /// Directory to save every match to, named by fingerprint
+    ///
+    /// Without this, streaming mode discards matches after printing them
+    /// (the original behavior) and non-streaming mode still saves to the
+    /// fixed `<file_stem>` / `<file_stem>.pub` pair. With it, every match
+    /// -- in either mode -- is saved under `DIR` as `<fingerprint-prefix>`
+    /// and `<fingerprint-prefix>.pub`, so a long streaming run can collect
+    /// many distinct vanity keys unattended instead of losing them.
+    #[arg(long, help = "Save every match into DIR instead of discarding/overwriting")]
+    output_dir: Option<std::path::PathBuf>,
+}
+
+/// Subcommands available alongside the default search mode.
+#[derive(Subcommand, Clone)]
+enum Command {
+    /// Measure key-generation throughput and, with `--regex`, estimate match time
+    Benchmark(BenchmarkArgs),
 }
 
 /// Global atomic counter tracking the total number of keys processed across all threads
@@ -119,113 +227,18 @@ struct Args {
 /// The counter uses `SeqCst` ordering to ensure consistency across threads.
 static COUNTER: AtomicI64 = AtomicI64::new(0);
 
-/// Generates a new ED25519 key pair using cryptographically secure random number generation
-///
-/// This function creates a fresh ED25519 signing key using the thread-local random number
-/// generator and derives the corresponding verifying (public) key from it.
-///
-/// # Returns
-///
-/// A tuple containing:
-/// - `SigningKey`: The private key used for signing operations
-/// - `VerifyingKey`: The public key derived from the signing key
-///
-/// # Security
-///
-/// This function uses `rand::thread_rng()` which provides cryptographically secure
-/// random numbers suitable for key generation. Each call produces a unique,
-/// unpredictable key pair.
-///
-/// # Examples
-///
-/// ```no_run
-/// let (signing_key, verifying_key) = generate_key_pair();
-/// // signing_key: used for SSH authentication
-/// // verifying_key: distributed to servers in authorized_keys
-/// ```
-fn generate_key_pair() -> (SigningKey, VerifyingKey) {
-    let signing_key = SigningKey::from_bytes(&rand::random());
-    let verifying_key = signing_key.verifying_key();
-    (signing_key, verifying_key)
-}
-
-/// Converts an ED25519 public key to OpenSSH authorized_keys format
-///
-/// This function takes a raw ED25519 verifying key and converts it to the
-/// standard OpenSSH public key format that can be added to `~/.ssh/authorized_keys`
-/// files on SSH servers.
-///
-/// # Arguments
-///
-/// * `public_key` - A reference to the ED25519 verifying key to convert
-///
-/// # Returns
-///
-/// A `String` containing the public key in OpenSSH format, which looks like:
-/// `ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAA... [optional comment]`
-///
-/// # Examples
-///
-/// ```no_run
-/// let (_, verifying_key) = generate_key_pair();
-/// let authorized_key = get_authorized_key(&verifying_key);
-/// // authorized_key can now be appended to ~/.ssh/authorized_keys
-/// ```
-fn get_authorized_key(public_key: &VerifyingKey) -> String {
-    use ssh_key::{public::Ed25519PublicKey, public::KeyData};
-
-    // Ed25519PublicKey is a newtype wrapper around [u8; 32]
-    let ed25519_key = Ed25519PublicKey(*public_key.as_bytes());
-    let key_data = KeyData::Ed25519(ed25519_key);
-    let ssh_public_key = ssh_key::PublicKey::new(key_data, "Generated by SSHChic");
-    ssh_public_key.to_string()
-}
-
-/// Calculates the SHA256 fingerprint of an ED25519 public key
-///
-/// This function computes the SHA256 hash of the raw public key bytes and
-/// returns it as a base64-encoded string. This fingerprint format is commonly
-/// used for key verification and identification.
-///
-/// # Arguments
-///
-/// * `public_key` - A reference to the ED25519 verifying key to fingerprint
-///
-/// # Returns
-///
-/// A `String` containing the base64-encoded SHA256 hash of the public key.
-/// This is the same format displayed by `ssh-keygen -l` when prefixed with "SHA256:".
-///
-/// # Examples
-///
-/// ```no_run
-/// let (_, verifying_key) = generate_key_pair();
-/// let fingerprint = get_fingerprint(&verifying_key);
-/// println!("Key fingerprint: SHA256:{}", fingerprint);
-/// ```
-///
-/// # Note
-///
-/// The fingerprint is computed from the raw key bytes, not the OpenSSH format.
-/// This matches the standard SSH fingerprint calculation.
-fn get_fingerprint(public_key: &VerifyingKey) -> String {
-    use base64::{engine::general_purpose, Engine as _};
-    use sha2::{Digest, Sha256};
-
-    let mut hasher = Sha256::new();
-    hasher.update(public_key.as_bytes());
-    general_purpose::STANDARD.encode(hasher.finalize())
-}
-
-/// Worker thread function that continuously generates and tests SSH keys against a regex pattern
+/// Worker thread function that continuously generates and tests SSH keys against a set of regex patterns
 ///
 /// This is the core search function executed by each worker thread. It runs in a loop,
-/// generating ED25519 key pairs and testing them against the provided regex pattern.
-/// When a match is found, it displays the keys and optionally saves them to files.
+/// generating key pairs of the algorithm selected by `args.algorithm` and testing them
+/// against every pattern in `regex_set` in one pass. When a match is found, it displays
+/// the keys, reports which pattern(s) matched, and optionally saves them to files.
 ///
 /// # Arguments
 ///
-/// * `regex` - The compiled regex pattern to match against
+/// * `regex_set` - The compiled set of regex patterns to match against, in the same order
+///   as `args.regex` (the original, not case-folded, pattern strings are what gets reported
+///   as matched, so the output echoes back exactly what the user typed)
 /// * `args` - Command-line arguments controlling match behavior
 /// * `running` - Atomic flag to signal when the thread should terminate
 ///
@@ -233,12 +246,13 @@ fn get_fingerprint(public_key: &VerifyingKey) -> String {
 ///
 /// For each iteration:
 /// 1. Increments the global `COUNTER` atomically
-/// 2. Generates a new ED25519 key pair
+/// 2. Generates a new key pair via `args.algorithm.generate()`
 /// 3. Tests against either fingerprint or public key (based on `args.fingerprint`)
 /// 4. On match:
-///    - Prints the private key, public key, and fingerprint
-///    - In non-streaming mode: saves to `id_ed25519` and `id_ed25519.pub`, then exits
-///    - In streaming mode: continues searching for more matches
+///    - Prints the private key, public key, fingerprint, and matched pattern(s)
+///    - If `--output-dir` is set: saves to `<dir>/<fingerprint-prefix>[.pub]`
+///    - Otherwise in non-streaming mode: saves to `<file_stem>` and `<file_stem>.pub`, then exits
+///    - In streaming mode without `--output-dir`: continues searching without saving
 ///
 /// # Thread Safety
 ///
@@ -248,62 +262,57 @@ fn get_fingerprint(public_key: &VerifyingKey) -> String {
 /// # Examples
 ///
 /// ```no_run
-/// let regex = Regex::new("SSH$").unwrap();
+/// let regex_set = RegexSet::new(["SSH$"]).unwrap();
 /// let args = Args { /* ... */ };
 /// let running = Arc::new(AtomicBool::new(true));
 ///
 /// // Spawn worker thread
 /// thread::spawn(move || {
-///     find_ssh_keys(&regex, &args, running);
+///     find_ssh_keys(&regex_set, &args, running);
 /// });
 /// ```
-fn find_ssh_keys(regex: &Regex, args: &Args, running: Arc<AtomicBool>) {
+fn find_ssh_keys(regex_set: &RegexSet, args: &Args, running: Arc<AtomicBool>) {
     while running.load(Ordering::SeqCst) {
         // Increment the global counter atomically
         COUNTER.fetch_add(1, Ordering::SeqCst);
-        let (signing_key, verifying_key) = generate_key_pair();
+        let key = args.algorithm.generate();
 
         // Match against either fingerprint or public key based on args
-        let matched = if args.fingerprint {
-            regex.is_match(&get_fingerprint(&verifying_key))
+        let haystack = if args.fingerprint {
+            key.fingerprint(args.fingerprint_type)
         } else {
-            regex.is_match(&get_authorized_key(&verifying_key))
+            key.to_openssh_public()
         };
+        let matched_patterns: Vec<&str> = regex_set
+            .matches(&haystack)
+            .into_iter()
+            .map(|i| args.regex[i].as_str())
+            .collect();
 
-        if matched {
+        if !matched_patterns.is_empty() {
             println!("{}", "\nMatch found!".green());
             println!("Total keys processed: {}", COUNTER.load(Ordering::SeqCst));
+            println!("Matched pattern(s): {}", matched_patterns.join(", "));
 
-            // Convert to OpenSSH private key format
-            use ssh_key::private::{Ed25519Keypair, Ed25519PrivateKey, KeypairData};
-            use ssh_key::public::Ed25519PublicKey;
-
-            // Ed25519PrivateKey and Ed25519PublicKey are newtype wrappers
-            let private_bytes = Ed25519PrivateKey(signing_key.to_bytes());
-            let public_bytes = Ed25519PublicKey(*verifying_key.as_bytes());
-
-            let keypair = Ed25519Keypair {
-                private: private_bytes,
-                public: public_bytes,
-            };
-            let keypair_data = KeypairData::Ed25519(keypair);
-
-            let private_key = PrivateKey::new(keypair_data, "Generated by SSHChic")
-                .expect("Failed to create private key");
-
-            let public_key_str = get_authorized_key(&verifying_key);
-            let private_key_str = private_key
-                .to_openssh(LineEnding::LF)
-                .expect("Failed to encode private key");
+            let public_key_str = key.to_openssh_public();
+            let private_key_str =
+                key.to_openssh_private(args.passphrase.as_deref().map(|p| (p, args.kdf_rounds)));
+            let fingerprint = key.fingerprint(args.fingerprint_type);
 
             println!("\nPrivate key:\n{}", private_key_str);
             println!("Public key:\n{}", public_key_str);
-            println!("Fingerprint: SHA256:{}", get_fingerprint(&verifying_key));
+            println!("Fingerprint: {}", fingerprint);
+
+            if let Some(dir) = &args.output_dir {
+                save_match(dir, &key, &public_key_str, &private_key_str);
+            } else if !args.streaming {
+                let stem = args.algorithm.file_stem();
+                fs::write(stem, &private_key_str).expect("Failed to write private key");
+                fs::write(format!("{stem}.pub"), &public_key_str)
+                    .expect("Failed to write public key");
+            }
 
-            // Save keys to files unless in streaming mode
             if !args.streaming {
-                fs::write("id_ed25519", private_key_str).expect("Failed to write private key");
-                fs::write("id_ed25519.pub", public_key_str).expect("Failed to write public key");
                 running.store(false, Ordering::SeqCst);
                 break;
             }
@@ -311,6 +320,29 @@ fn find_ssh_keys(regex: &Regex, args: &Args, running: Arc<AtomicBool>) {
     }
 }
 
+/// Saves a match into `--output-dir`, naming the files after a sanitized
+/// prefix of the key's SHA256 fingerprint (always SHA256 regardless of
+/// `--fingerprint-type`, so filenames stay short and predictable) rather
+/// than a fixed `id_*` stem, since an output directory is meant to collect
+/// many distinct matches without overwriting earlier ones.
+fn save_match(dir: &std::path::Path, key: &keys::GeneratedKey, public_key_str: &str, private_key_str: &str) {
+    fs::create_dir_all(dir).expect("Failed to create --output-dir");
+
+    let fingerprint = key.fingerprint(HashAlg::Sha256);
+    let digest = fingerprint.split(':').next_back().unwrap_or(&fingerprint);
+    let prefix: String = digest
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .take(12)
+        .collect();
+
+    let private_path = dir.join(&prefix);
+    let public_path = dir.join(format!("{prefix}.pub"));
+    fs::write(&private_path, private_key_str).expect("Failed to write private key");
+    fs::write(&public_path, public_key_str).expect("Failed to write public key");
+    println!("Saved to {}", private_path.display());
+}
+
 /// Calculates an exponential moving average for smoothing key generation rate metrics
 ///
 /// This function implements an exponential moving average (EMA) with a configurable time window.
@@ -355,30 +387,58 @@ fn find_ssh_keys(regex: &Regex, args: &Args, running: Arc<AtomicBool>) {
 ///
 /// SSHChic uses a 5-second time window with updates every 250ms to balance
 /// responsiveness with stability in the displayed key generation rate.
-fn exp_moving_average(value: f64, old_value: f64, delta_time: f64, time_window: f64) -> f64 {
+pub(crate) fn exp_moving_average(value: f64, old_value: f64, delta_time: f64, time_window: f64) -> f64 {
     let alpha = 1.0 - (-delta_time / time_window).exp();
     alpha * value + (1.0 - alpha) * old_value
 }
 
 fn main() {
     // Parse command line arguments
-    let args = Args::parse();
-    let regex_str = if args.insensitive {
-        format!("(?i){}", args.regex)
-    } else {
-        args.regex.clone()
-    };
+    let mut args = Args::parse();
 
-    // Compile regex pattern
-    let regex = match Regex::new(&regex_str) {
-        Ok(re) => re,
+    if let Some(Command::Benchmark(bench_args)) = &args.command {
+        benchmark::run(bench_args);
+        return;
+    }
+
+    // `--passphrase` with no value comes through as `Some("")` (see
+    // `default_missing_value`); prompt for the real passphrase without
+    // echoing it, so it never lands in shell history or `ps` output.
+    if let Some(passphrase) = &args.passphrase {
+        if passphrase.is_empty() {
+            let prompted = rpassword::prompt_password("Passphrase: ")
+                .expect("failed to read passphrase");
+            args.passphrase = Some(prompted);
+        }
+    }
+
+    if args.regex.is_empty() {
+        eprintln!("--regex is required in search mode (see `sshchic benchmark` for the benchmark mode)");
+        std::process::exit(1);
+    }
+    let patterns: Vec<String> = args
+        .regex
+        .iter()
+        .map(|pattern| {
+            if args.insensitive {
+                format!("(?i){pattern}")
+            } else {
+                pattern.clone()
+            }
+        })
+        .collect();
+
+    // Compile all patterns into a single set, tested against each key in one pass
+    let regex_set = match RegexSet::new(&patterns) {
+        Ok(set) => set,
         Err(e) => {
             eprintln!("Invalid regex pattern: {}", e);
             std::process::exit(1);
         }
     };
 
-    println!("Using regex pattern: {}", regex_str);
+    println!("Using regex pattern(s): {}", patterns.join(", "));
+    println!("Using algorithm: {}", args.algorithm);
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
 
@@ -393,12 +453,12 @@ fn main() {
     let mut handles = vec![];
 
     for _ in 0..num_threads {
-        let regex_clone = regex.clone();
+        let regex_set_clone = regex_set.clone();
         let args_clone = args.clone();
         let running_clone = running.clone();
 
         handles.push(thread::spawn(move || {
-            find_ssh_keys(&regex_clone, &args_clone, running_clone);
+            find_ssh_keys(&regex_set_clone, &args_clone, running_clone);
         }));
     }
 
@@ -425,7 +485,14 @@ fn main() {
             "Keys processed: {}",
             format_size(current_counter as u64, DECIMAL)
         );
-        print!(" | Rate: {:.2} kKeys/s", avg_key_rate / elapsed / 1000.0);
+        let rate = avg_key_rate / elapsed;
+        if rate < 1000.0 {
+            // Slow algorithms (notably RSA) can run at only a few keys/sec,
+            // where "0.00 kKeys/s" would be a useless reading.
+            print!(" | Rate: {:.2} Keys/s", rate);
+        } else {
+            print!(" | Rate: {:.2} kKeys/s", rate / 1000.0);
+        }
 
         // Calculate moving average of key generation rate
         avg_key_rate = exp_moving_average(